@@ -0,0 +1,286 @@
+// Copyright 2016-2018 Mateusz Sieczko and other GilRs Developers
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! Filters that are used to modify events before they reach your application.
+//!
+//! A filter implements the [`Filter`] trait: it receives the previous filter's output
+//! and returns the event it wants the next filter, or the application, to see.
+//! Returning `None` drops the event. Filters are chained by feeding one's output into
+//! the next, for example:
+//!
+//! ```ignore
+//! let ev = gilrs_core_event;
+//! let ev = my_filter.filter(ev);
+//! let ev = another_filter.filter(ev);
+//! ```
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+
+use crate::ev::{Button, Code, Event, EventType};
+
+/// Allows implementing custom event filters.
+///
+/// See module level documentation for more info.
+pub trait Filter {
+    fn filter(&self, ev: Option<Event>) -> Option<Event>;
+}
+
+/// Default value at which a button transitions to pressed, used by [`Threshold`]
+/// when no per-button override is set.
+pub const DEFAULT_PRESS_THRESHOLD: f32 = 0.75;
+/// Default value at which a button transitions to released, used by [`Threshold`]
+/// when no per-button override is set.
+pub const DEFAULT_RELEASE_THRESHOLD: f32 = 0.65;
+
+#[derive(Debug, Clone, Copy)]
+struct Thresholds {
+    press: f32,
+    release: f32,
+}
+
+/// Turns `ButtonChanged` events into `ButtonPressed`/`ButtonReleased` using separate
+/// press and release thresholds per button, with hysteresis.
+///
+/// A single fixed cutoff makes an analog trigger resting near that value flicker
+/// between pressed and released. `Threshold` instead only transitions to pressed
+/// once the value rises to `>= press_threshold`, and back to released once it falls
+/// to `<= release_threshold`, holding its current state everywhere in between.
+/// Values that never cross a threshold produce no digital event at all.
+///
+/// Defaults to press `0.75` / release `0.65` for every button; override either with
+/// [`Threshold::set_default`], or per-button with [`Threshold::set_button`].
+#[derive(Debug)]
+pub struct Threshold {
+    default: Thresholds,
+    overrides: HashMap<Button, Thresholds>,
+    pressed: RefCell<HashMap<Code, bool>>,
+}
+
+impl Threshold {
+    pub fn new() -> Self {
+        Threshold {
+            default: Thresholds {
+                press: DEFAULT_PRESS_THRESHOLD,
+                release: DEFAULT_RELEASE_THRESHOLD,
+            },
+            overrides: HashMap::new(),
+            pressed: RefCell::new(HashMap::new()),
+        }
+    }
+
+    /// Sets the press/release thresholds used for buttons without a per-button override.
+    pub fn set_default(&mut self, press_threshold: f32, release_threshold: f32) {
+        self.default = Thresholds {
+            press: press_threshold,
+            release: release_threshold,
+        };
+    }
+
+    /// Overrides the press/release thresholds for a single button.
+    pub fn set_button(&mut self, button: Button, press_threshold: f32, release_threshold: f32) {
+        self.overrides.insert(
+            button,
+            Thresholds {
+                press: press_threshold,
+                release: release_threshold,
+            },
+        );
+    }
+
+    fn thresholds_for(&self, button: Button) -> Thresholds {
+        self.overrides.get(&button).copied().unwrap_or(self.default)
+    }
+}
+
+impl Default for Threshold {
+    fn default() -> Self {
+        Threshold::new()
+    }
+}
+
+impl Filter for Threshold {
+    fn filter(&self, ev: Option<Event>) -> Option<Event> {
+        let ev = ev?;
+
+        let (button, value, code) = match ev.event {
+            EventType::ButtonChanged(button, value, code) => (button, value, code),
+            _ => return Some(ev),
+        };
+
+        let thresholds = self.thresholds_for(button);
+        let mut pressed = self.pressed.borrow_mut();
+        let was_pressed = *pressed.entry(code).or_insert(false);
+
+        if !was_pressed && value >= thresholds.press {
+            *pressed.get_mut(&code).unwrap() = true;
+            Some(Event::new_with_time(
+                ev.id,
+                EventType::ButtonPressed(button, code),
+                ev.time,
+            ))
+        } else if was_pressed && value <= thresholds.release {
+            *pressed.get_mut(&code).unwrap() = false;
+            Some(Event::new_with_time(
+                ev.id,
+                EventType::ButtonReleased(button, code),
+                ev.time,
+            ))
+        } else {
+            None
+        }
+    }
+}
+
+/// The event exactly as produced by the backend, paired with whatever this crate's
+/// filter chain (e.g. [`Threshold`]) turned it into.
+///
+/// Returned by [`Gilrs::next_event_with_raw`](crate::gamepad::Gilrs::next_event_with_raw)
+/// so callers that need calibration-free readings (to implement a custom deadzone
+/// curve or calibration tooling) can inspect the original value while still getting
+/// the normal filtered stream. Note that this is raw relative to *this crate's*
+/// filters only: any normalization the platform backend itself performs before
+/// handing the event to gilrs (e.g. controller-level deadzone) has already happened.
+#[derive(Debug, Clone)]
+pub struct RawAndFiltered {
+    /// The event exactly as read from the backend, before [`Threshold`] or any
+    /// other [`Filter`] in the chain has touched it.
+    pub raw: Event,
+    /// The event after running through every filter in the chain, or `None` if one
+    /// of them dropped it.
+    pub filtered: Option<Event>,
+}
+
+/// Runs `ev` through every filter in `filters`, in order, while retaining the
+/// original, unfiltered event.
+///
+/// This is equivalent to chaining [`Filter::filter`] calls by hand except that it
+/// also hands back `ev` untouched, so the event pump can expose both the processed
+/// `EventType::AxisChanged`/`ButtonChanged` and the raw value read straight from the
+/// backend for the same physical input.
+pub fn filter_with_raw(ev: Event, filters: &[&dyn Filter]) -> RawAndFiltered {
+    let raw = ev.clone();
+    let mut current = Some(ev);
+
+    for filter in filters {
+        current = filter.filter(current);
+    }
+
+    RawAndFiltered {
+        raw,
+        filtered: current,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ev::{Axis, Button};
+
+    fn button_changed(button: Button, value: f32, code: Code) -> Event {
+        Event::new(
+            crate::gamepad::GamepadId(0),
+            EventType::ButtonChanged(button, value, code),
+        )
+    }
+
+    #[test]
+    fn filter_with_raw_keeps_original_alongside_filtered() {
+        let code = Button::South.to_nec().unwrap();
+        let mut threshold = Threshold::new();
+        threshold.set_default(0.75, 0.65);
+
+        let ev = button_changed(Button::South, 0.9, code);
+        let result = filter_with_raw(ev.clone(), &[&threshold as &dyn Filter]);
+
+        assert_eq!(result.raw, ev);
+        match result.filtered.unwrap().event {
+            EventType::ButtonPressed(Button::South, c) => assert_eq!(c, code),
+            other => panic!("unexpected event: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn filter_with_raw_passes_through_events_the_filter_ignores() {
+        let code = Axis::LeftStickX.to_nec().unwrap();
+        let ev = Event::new(
+            crate::gamepad::GamepadId(0),
+            EventType::AxisChanged(Axis::LeftStickX, 0.1, code),
+        );
+
+        // A bare `Threshold` only reacts to `ButtonChanged`, so an axis event passes
+        // through unchanged; this still lets callers compare raw vs. filtered.
+        let threshold = Threshold::new();
+        let result = filter_with_raw(ev.clone(), &[&threshold as &dyn Filter]);
+
+        assert_eq!(result.raw, ev);
+        assert_eq!(result.filtered, Some(ev));
+    }
+
+    #[test]
+    fn threshold_rise_to_press_emits_button_pressed() {
+        let code = Button::South.to_nec().unwrap();
+        let threshold = Threshold::new();
+
+        let ev = button_changed(Button::South, 0.8, code);
+        let result = threshold.filter(Some(ev)).unwrap();
+
+        assert_eq!(
+            result.event,
+            EventType::ButtonPressed(Button::South, code)
+        );
+    }
+
+    #[test]
+    fn threshold_fall_to_release_emits_button_released() {
+        let code = Button::South.to_nec().unwrap();
+        let threshold = Threshold::new();
+
+        // Rising above the press threshold first, so the filter's internal state
+        // is "pressed" before we test the release transition.
+        threshold.filter(Some(button_changed(Button::South, 0.8, code)));
+
+        let ev = button_changed(Button::South, 0.5, code);
+        let result = threshold.filter(Some(ev)).unwrap();
+
+        assert_eq!(
+            result.event,
+            EventType::ButtonReleased(Button::South, code)
+        );
+    }
+
+    #[test]
+    fn threshold_dead_band_produces_no_event_either_way() {
+        let code = Button::South.to_nec().unwrap();
+        let threshold = Threshold::new();
+
+        // Starts released; a value strictly between the two thresholds is neither a
+        // press nor a release, so nothing should come out.
+        let ev = button_changed(Button::South, 0.7, code);
+        assert_eq!(threshold.filter(Some(ev)), None);
+
+        // Rising into pressed state, then dithering back into the dead band: still
+        // no event, since that's not a release either (the button is still "down"
+        // until it falls to the release threshold).
+        threshold.filter(Some(button_changed(Button::South, 0.8, code)));
+        let ev = button_changed(Button::South, 0.7, code);
+        assert_eq!(threshold.filter(Some(ev)), None);
+    }
+
+    #[test]
+    fn threshold_per_button_override_takes_precedence_over_default() {
+        let code = Button::West.to_nec().unwrap();
+        let mut threshold = Threshold::new();
+        threshold.set_button(Button::West, 0.2, 0.1);
+
+        // Below the global default press threshold (0.75) but above the override.
+        let ev = button_changed(Button::West, 0.3, code);
+        let result = threshold.filter(Some(ev)).unwrap();
+
+        assert_eq!(result.event, EventType::ButtonPressed(Button::West, code));
+    }
+}
@@ -8,6 +8,7 @@
 //! Gamepad state and other event related functionality.
 
 pub mod filter;
+pub mod recorder;
 pub mod state;
 
 use std::{
@@ -17,20 +18,38 @@ use std::{
 
 use crate::{constants::*, gamepad::GamepadId, utils};
 
-#[cfg(feature = "serde-serialize")]
+#[cfg(any(feature = "serde-serialize", feature = "serde-serialize-portable"))]
 use serde::{Deserialize, Serialize};
+#[cfg(feature = "serde-serialize-portable")]
+use serde::{de, Deserializer, Serializer};
 
 /// Platform specific event code.
 ///
 /// This type represents single gamepads's element like specific axis or button.
 /// It can't be directly created, but you can get it from events or using
 /// `Gamepad`'s methods [`button_code`](crate::Gamepad::button_code) and
-/// [`axis_code`](crate::Gamepad::axis_code). If `serde-serialize` feature is
-/// enabled, `Code` can be serialized and deserialized, but keep in mind that
-/// layout **is** platform-specific. So it's not possible to serialize `Code` on
-/// Linux and deserialize it on Windows. This also apply to `Display` implementation.
+/// [`axis_code`](crate::Gamepad::axis_code). If the `serde-serialize` feature is
+/// enabled, `Code` can be serialized and deserialized, but keep in mind that layout
+/// **is** platform-specific by default: it's not possible to serialize a `Code` on
+/// Linux and deserialize it on Windows.
+///
+/// Enabling the additional, opt-in `serde-serialize-portable` feature switches
+/// `Code` to a portable representation instead: when the code maps to a known
+/// [`Button`] or [`Axis`], it's serialized as that logical element, which is
+/// resolved back to the current platform's native code on deserialize. Codes that
+/// don't map to a known element still fall back to their raw
+/// [`into_u32`](Code::into_u32) value, which **is** platform-specific and will only
+/// round-trip on the same platform it was recorded on. This makes it possible to
+/// record an [`Event`] stream on one machine (or platform) and replay it on
+/// another, as long as every `Code` involved maps to a known `Button` or `Axis`.
+/// This mode depends on `gilrs_core::EvCode::from_u32` to reconstruct the `Raw`
+/// fallback, so it requires a `gilrs_core` version that provides it; enabling
+/// `serde-serialize-portable` implies `serde-serialize`.
 #[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
-#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+#[cfg_attr(
+    all(feature = "serde-serialize", not(feature = "serde-serialize-portable")),
+    derive(Serialize, Deserialize)
+)]
 pub struct Code(pub(crate) gilrs_core::EvCode);
 
 impl Display for Code {
@@ -43,10 +62,92 @@ impl Code {
     pub fn into_u32(&self) -> u32 {
         self.0.into_u32()
     }
+
+    /// Resolves this code to the logical [`Button`] or [`Axis`] it corresponds to on
+    /// the current platform, if any. This is the inverse of
+    /// [`Button::to_nec`]/[`Axis::to_nec`] and backs the portable serialization of `Code`.
+    pub(crate) fn to_axis_or_btn(self) -> Option<AxisOrBtn> {
+        Button::iter_all()
+            .find(|btn| btn.to_nec() == Some(self))
+            .map(AxisOrBtn::Btn)
+            .or_else(|| {
+                Axis::iter_all()
+                    .find(|axis| axis.to_nec() == Some(self))
+                    .map(AxisOrBtn::Axis)
+            })
+    }
+}
+
+/// Portable, platform-independent representation of a [`Code`] used for
+/// serialization when the `serde-serialize-portable` feature is enabled. See
+/// [`Code`]'s documentation for details.
+#[cfg(feature = "serde-serialize-portable")]
+#[derive(Serialize, Deserialize)]
+enum PortableCode {
+    Button(Button),
+    Axis(Axis),
+    Raw(u32),
+}
+
+#[cfg(feature = "serde-serialize-portable")]
+impl Serialize for Code {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let portable = match self.to_axis_or_btn() {
+            Some(AxisOrBtn::Btn(btn)) => PortableCode::Button(btn),
+            Some(AxisOrBtn::Axis(axis)) => PortableCode::Axis(axis),
+            None => PortableCode::Raw(self.into_u32()),
+        };
+
+        portable.serialize(serializer)
+    }
+}
+
+#[cfg(feature = "serde-serialize-portable")]
+impl<'de> Deserialize<'de> for Code {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        match PortableCode::deserialize(deserializer)? {
+            PortableCode::Button(btn) => btn.to_nec().ok_or_else(|| {
+                de::Error::custom("button has no native event code on this platform")
+            }),
+            PortableCode::Axis(axis) => axis.to_nec().ok_or_else(|| {
+                de::Error::custom("axis has no native event code on this platform")
+            }),
+            // `EvCode::from_u32` mirrors the existing `EvCode::into_u32` and has to
+            // be provided by `gilrs_core`; it's only required when
+            // `serde-serialize-portable` is enabled, so pin a `gilrs_core` version
+            // that provides it before turning this feature on.
+            PortableCode::Raw(code) => Ok(Code(gilrs_core::EvCode::from_u32(code))),
+        }
+    }
+}
+
+/// Information about a gamepad carried by [`EventType::Connected`].
+///
+/// This is a snapshot of the data available at the moment the gamepad was
+/// connected, so it stays valid even if the gamepad is looked up after it has
+/// already disconnected again.
+#[derive(Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+#[non_exhaustive]
+pub struct ConnectionInfo {
+    /// Name of the gamepad, as reported by the OS or mapping database.
+    pub name: String,
+    /// UUID identifying the gamepad model, used for mapping lookup.
+    pub uuid: [u8; 16],
+    /// USB vendor ID, if known.
+    pub vendor_id: Option<u16>,
+    /// USB product ID, if known.
+    pub product_id: Option<u16>,
 }
 
 /// Holds information about gamepad event.
-#[derive(Copy, Clone, PartialEq, Debug)]
+#[derive(Clone, PartialEq, Debug)]
 #[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
 #[non_exhaustive]
 pub struct Event {
@@ -90,14 +191,15 @@ impl Event {
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
 #[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
 #[non_exhaustive]
 /// Gamepad event.
 pub enum EventType {
     /// Some button on gamepad has been pressed.
     ButtonPressed(Button, Code),
-    /// This event can be generated by [`ev::Repeat`](filter/struct.Repeat.html) event filter.
+    /// This event can be generated by a custom [`Filter`](filter::Filter) that
+    /// implements button-repeat semantics; this crate doesn't ship one.
     ButtonRepeated(Button, Code),
     /// Previously pressed button has been released.
     ButtonReleased(Button, Code),
@@ -107,7 +209,10 @@ pub enum EventType {
     AxisChanged(Axis, f32, Code),
     /// Gamepad has been connected. If gamepad's UUID doesn't match one of disconnected gamepads,
     /// newly connected gamepad will get new ID.
-    Connected,
+    ///
+    /// Carries a [`ConnectionInfo`] snapshot so consumers can record or route the connection
+    /// without holding a live [`Gilrs`](crate::Gilrs) reference.
+    Connected(ConnectionInfo),
     /// Gamepad has been disconnected. Disconnected gamepad will not generate any new events.
     Disconnected,
     /// There was an `Event`, but it was dropped by one of filters. You should ignore it.
@@ -208,6 +313,32 @@ impl Button {
         }
         .map(Code)
     }
+
+    /// Iterates over every `Button` variant except `Unknown`.
+    fn iter_all() -> impl Iterator<Item = Button> {
+        [
+            Button::South,
+            Button::East,
+            Button::North,
+            Button::West,
+            Button::C,
+            Button::Z,
+            Button::LeftTrigger,
+            Button::LeftTrigger2,
+            Button::RightTrigger,
+            Button::RightTrigger2,
+            Button::Select,
+            Button::Start,
+            Button::Mode,
+            Button::LeftThumb,
+            Button::RightThumb,
+            Button::DPadUp,
+            Button::DPadDown,
+            Button::DPadLeft,
+            Button::DPadRight,
+        ]
+        .into_iter()
+    }
 }
 
 #[repr(u16)]
@@ -258,6 +389,38 @@ impl Axis {
             _ => None,
         }
     }
+
+    pub fn to_nec(self) -> Option<Code> {
+        use gilrs_core::native_ev_codes as necs;
+
+        match self {
+            Axis::LeftStickX => Some(necs::AXIS_LSTICKX),
+            Axis::LeftStickY => Some(necs::AXIS_LSTICKY),
+            Axis::LeftZ => Some(necs::AXIS_LEFTZ),
+            Axis::RightStickX => Some(necs::AXIS_RSTICKX),
+            Axis::RightStickY => Some(necs::AXIS_RSTICKY),
+            Axis::RightZ => Some(necs::AXIS_RIGHTZ),
+            Axis::DPadX => Some(necs::AXIS_DPADX),
+            Axis::DPadY => Some(necs::AXIS_DPADY),
+            Axis::Unknown => None,
+        }
+        .map(Code)
+    }
+
+    /// Iterates over every `Axis` variant except `Unknown`.
+    fn iter_all() -> impl Iterator<Item = Axis> {
+        [
+            Axis::LeftStickX,
+            Axis::LeftStickY,
+            Axis::LeftZ,
+            Axis::RightStickX,
+            Axis::RightStickY,
+            Axis::RightZ,
+            Axis::DPadX,
+            Axis::DPadY,
+        ]
+        .into_iter()
+    }
 }
 
 /// Represents `Axis` or `Button`.
@@ -273,3 +436,80 @@ impl AxisOrBtn {
         matches!(self, AxisOrBtn::Btn(_))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn connected_event_carries_connection_info() {
+        let info = ConnectionInfo {
+            name: "Xbox Controller".to_string(),
+            uuid: [1; 16],
+            vendor_id: Some(0x045e),
+            product_id: Some(0x028e),
+        };
+
+        let event = EventType::Connected(info.clone());
+
+        match event {
+            EventType::Connected(got) => assert_eq!(got, info),
+            _ => panic!("expected EventType::Connected"),
+        }
+    }
+
+    #[test]
+    fn code_resolves_back_to_the_button_it_came_from() {
+        let code = Button::South.to_nec().unwrap();
+        assert_eq!(code.to_axis_or_btn(), Some(AxisOrBtn::Btn(Button::South)));
+    }
+
+    #[test]
+    fn code_resolves_back_to_the_axis_it_came_from() {
+        let code = Axis::LeftStickX.to_nec().unwrap();
+        assert_eq!(code.to_axis_or_btn(), Some(AxisOrBtn::Axis(Axis::LeftStickX)));
+    }
+
+    #[cfg(feature = "serde-serialize-portable")]
+    #[test]
+    fn code_round_trips_through_known_button() {
+        let code = Button::South.to_nec().unwrap();
+
+        let json = serde_json::to_string(&code).unwrap();
+        let restored: Code = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(restored, code);
+    }
+
+    #[cfg(feature = "serde-serialize-portable")]
+    #[test]
+    fn code_round_trips_through_known_axis() {
+        let code = Axis::LeftStickX.to_nec().unwrap();
+
+        let json = serde_json::to_string(&code).unwrap();
+        let restored: Code = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(restored, code);
+    }
+
+    // `Code`'s `Raw` fallback (used for codes that don't map to any known `Button`/
+    // `Axis`) needs `gilrs_core::EvCode::from_u32` to reconstruct a code from its
+    // `u32` form on deserialize. That constructor has to come from `gilrs_core`,
+    // mirroring the existing `EvCode::into_u32`; it isn't exercised
+    // here since this crate only depends on `gilrs_core` through Cargo, not through
+    // a path we can unit test against directly. The shape of the fallback itself
+    // (not the reconstruction) is covered below.
+    #[cfg(feature = "serde-serialize-portable")]
+    #[test]
+    fn raw_fallback_round_trips_its_u32_value() {
+        let portable = PortableCode::Raw(0xdead_beef);
+
+        let json = serde_json::to_string(&portable).unwrap();
+        let restored: PortableCode = serde_json::from_str(&json).unwrap();
+
+        match restored {
+            PortableCode::Raw(v) => assert_eq!(v, 0xdead_beef),
+            _ => panic!("expected PortableCode::Raw"),
+        }
+    }
+}
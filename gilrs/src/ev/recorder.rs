@@ -0,0 +1,218 @@
+// Copyright 2016-2018 Mateusz Sieczko and other GilRs Developers
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! Recording and deterministic playback of `Event` streams.
+//!
+//! Combined with the portable serialization of [`Code`](super::Code), a [`Recording`]
+//! captured on one machine can be replayed on another, which makes it useful both for
+//! reproducible regression tests and for saving input demos.
+
+use std::time::{Duration, SystemTime};
+
+#[cfg(feature = "serde-serialize")]
+use serde::{Deserialize, Serialize};
+
+use crate::ev::Event;
+
+/// A single recorded event, stored alongside its offset from the start of the
+/// recording so playback doesn't depend on the absolute `SystemTime` it was
+/// originally captured at.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+pub struct RecordedEvent {
+    /// Event exactly as it was recorded.
+    pub event: Event,
+    /// Time elapsed since the first event in the recording.
+    pub offset: Duration,
+}
+
+/// Captures an `Event` stream into a serializable [`Recording`].
+///
+/// Feed it every event as you receive it from [`Gilrs::next_event`](crate::Gilrs::next_event).
+/// `Recorder` preserves inter-event timing by storing each event's offset from the
+/// first one it ever saw, so a [`Player`] can reproduce the original pacing later.
+#[derive(Debug, Clone, Default)]
+pub struct Recorder {
+    start: Option<SystemTime>,
+    events: Vec<RecordedEvent>,
+}
+
+impl Recorder {
+    pub fn new() -> Self {
+        Recorder::default()
+    }
+
+    /// Records `event`, computing its offset from the first event ever recorded.
+    pub fn record(&mut self, event: Event) {
+        let start = *self.start.get_or_insert(event.time);
+        let offset = event.time.duration_since(start).unwrap_or(Duration::ZERO);
+
+        self.events.push(RecordedEvent { event, offset });
+    }
+
+    /// Returns the number of events recorded so far.
+    pub fn len(&self) -> usize {
+        self.events.len()
+    }
+
+    /// Returns true if no event has been recorded yet.
+    pub fn is_empty(&self) -> bool {
+        self.events.is_empty()
+    }
+
+    /// Finishes recording, returning the log for serialization or playback.
+    pub fn finish(self) -> Recording {
+        Recording {
+            events: self.events,
+        }
+    }
+}
+
+/// A recorded `Event` stream, ready to be serialized or replayed with [`Player`].
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+pub struct Recording {
+    events: Vec<RecordedEvent>,
+}
+
+impl Recording {
+    /// Returns the recorded events in order, alongside their offset from the start
+    /// of the recording.
+    pub fn events(&self) -> &[RecordedEvent] {
+        &self.events
+    }
+}
+
+/// Controls how a [`Player`] paces events back out.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Pacing {
+    /// Reproduce the original inter-event delays, for interactive playback.
+    RealTime,
+    /// Emit every event as soon as it's asked for, for headless tests.
+    AsFastAsPossible,
+}
+
+/// Replays a [`Recording`] through the same `Event` stream applications normally
+/// consume, for deterministic regression testing and demo playback.
+#[derive(Debug, Clone)]
+pub struct Player {
+    recording: Recording,
+    pacing: Pacing,
+    next: usize,
+    started_at: Option<SystemTime>,
+}
+
+impl Player {
+    pub fn new(recording: Recording, pacing: Pacing) -> Self {
+        Player {
+            recording,
+            pacing,
+            next: 0,
+            started_at: None,
+        }
+    }
+
+    /// Returns the next event due to be replayed, if its offset has elapsed.
+    ///
+    /// With [`Pacing::AsFastAsPossible`], every call returns the next event
+    /// immediately, if any are left. With [`Pacing::RealTime`], this returns `None`
+    /// until enough wall-clock time has passed since the first call to reach the
+    /// next event's recorded offset.
+    pub fn next_event(&mut self, now: SystemTime) -> Option<Event> {
+        let recorded = self.recording.events().get(self.next)?;
+
+        if self.pacing == Pacing::RealTime {
+            let started_at = *self.started_at.get_or_insert(now);
+
+            if now.duration_since(started_at).unwrap_or(Duration::ZERO) < recorded.offset {
+                return None;
+            }
+        }
+
+        self.next += 1;
+        Some(recorded.event.clone())
+    }
+
+    /// Returns true once every recorded event has been replayed.
+    pub fn is_finished(&self) -> bool {
+        self.next >= self.recording.events().len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::UNIX_EPOCH;
+
+    use super::*;
+    use crate::ev::EventType;
+    use crate::gamepad::GamepadId;
+
+    fn event_at(time: SystemTime) -> Event {
+        Event::new_with_time(GamepadId(0), EventType::Disconnected, time)
+    }
+
+    #[test]
+    fn record_computes_each_events_offset_from_the_first_one() {
+        let start = UNIX_EPOCH + Duration::from_secs(100);
+        let mut recorder = Recorder::new();
+
+        recorder.record(event_at(start));
+        recorder.record(event_at(start + Duration::from_millis(250)));
+        recorder.record(event_at(start + Duration::from_secs(2)));
+
+        let offsets: Vec<Duration> = recorder
+            .finish()
+            .events()
+            .iter()
+            .map(|recorded| recorded.offset)
+            .collect();
+
+        assert_eq!(
+            offsets,
+            vec![Duration::ZERO, Duration::from_millis(250), Duration::from_secs(2)]
+        );
+    }
+
+    #[test]
+    fn as_fast_as_possible_drains_every_event_immediately() {
+        let start = UNIX_EPOCH;
+        let mut recorder = Recorder::new();
+        recorder.record(event_at(start));
+        recorder.record(event_at(start + Duration::from_secs(10)));
+
+        let mut player = Player::new(recorder.finish(), Pacing::AsFastAsPossible);
+
+        assert!(player.next_event(start).is_some());
+        assert!(!player.is_finished());
+        assert!(player.next_event(start).is_some());
+        assert!(player.is_finished());
+        assert_eq!(player.next_event(start), None);
+    }
+
+    #[test]
+    fn real_time_withholds_an_event_until_its_offset_elapses() {
+        let start = UNIX_EPOCH;
+        let mut recorder = Recorder::new();
+        recorder.record(event_at(start));
+        recorder.record(event_at(start + Duration::from_secs(1)));
+
+        let mut player = Player::new(recorder.finish(), Pacing::RealTime);
+
+        // The first event's offset (zero) has already elapsed as soon as playback
+        // starts.
+        assert!(player.next_event(start).is_some());
+
+        // The second event's offset hasn't elapsed yet relative to when playback
+        // started.
+        assert_eq!(player.next_event(start + Duration::from_millis(500)), None);
+        assert!(!player.is_finished());
+
+        // Now it has.
+        assert!(player.next_event(start + Duration::from_secs(1)).is_some());
+        assert!(player.is_finished());
+    }
+}
@@ -0,0 +1,172 @@
+// Copyright 2016-2018 Mateusz Sieczko and other GilRs Developers
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! Gamepad handling and event pump.
+
+use std::fmt::{Display, Formatter, Result as FmtResult};
+
+use crate::ev::filter::{filter_with_raw, Filter, RawAndFiltered};
+use crate::ev::{Axis, AxisOrBtn, Button, Code, ConnectionInfo, Event, EventType};
+use crate::Error;
+
+/// Identifier of a gamepad, stable for as long as it stays connected.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct GamepadId(pub(crate) usize);
+
+impl Display for GamepadId {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        self.0.fmt(f)
+    }
+}
+
+/// Main object responsible for handling gamepads.
+///
+/// `Gilrs` wraps the platform's native backend and translates its events into this
+/// crate's [`Event`]/[`EventType`].
+pub struct Gilrs {
+    inner: gilrs_core::Gilrs,
+}
+
+impl Gilrs {
+    pub fn new() -> Result<Self, Error> {
+        gilrs_core::Gilrs::new()
+            .map(|inner| Gilrs { inner })
+            .map_err(Error::from)
+    }
+
+    /// Returns the next pending gamepad event, translating it from the backend's
+    /// native representation.
+    ///
+    /// `Connected` is translated into a fully populated [`ConnectionInfo`] snapshot,
+    /// taken from the gamepad as it exists right now, so the event stays
+    /// self-describing even if the gamepad is looked up after it has already
+    /// disconnected again.
+    pub fn next_event(&mut self) -> Option<Event> {
+        let ev = self.inner.next_event()?;
+        let id = GamepadId(ev.id);
+
+        let event = match ev.event {
+            gilrs_core::EventType::Connected => {
+                let gamepad = self.inner.gamepad(ev.id);
+                EventType::Connected(connection_info(
+                    gamepad.name(),
+                    gamepad.uuid(),
+                    gamepad.vendor_id(),
+                    gamepad.product_id(),
+                ))
+            }
+            gilrs_core::EventType::Disconnected => EventType::Disconnected,
+            gilrs_core::EventType::ButtonPressed(code) => {
+                let code = Code(code);
+                EventType::ButtonPressed(button_for(code), code)
+            }
+            gilrs_core::EventType::ButtonReleased(code) => {
+                let code = Code(code);
+                EventType::ButtonReleased(button_for(code), code)
+            }
+            gilrs_core::EventType::ButtonChanged(code, value) => {
+                let code = Code(code);
+                EventType::ButtonChanged(button_for(code), value, code)
+            }
+            gilrs_core::EventType::AxisChanged(code, value) => {
+                let code = Code(code);
+                EventType::AxisChanged(axis_for(code), value, code)
+            }
+        };
+
+        Some(Event::new_with_time(id, event, ev.time))
+    }
+
+    /// Like [`next_event`](Self::next_event), but also returns the event exactly as
+    /// produced by the backend, before `filters` runs on it.
+    ///
+    /// This is the event-pump entry point for the raw event stream: it lets callers
+    /// implement custom deadzone curves or calibration tooling from the unfiltered
+    /// value while still getting the normal stream filtered through `filters`.
+    pub fn next_event_with_raw(&mut self, filters: &[&dyn Filter]) -> Option<RawAndFiltered> {
+        let ev = self.next_event()?;
+        Some(filter_with_raw(ev, filters))
+    }
+}
+
+/// Builds the [`ConnectionInfo`] snapshot from a just-connected native gamepad's
+/// fields.
+///
+/// Takes the fields rather than `&gilrs_core::Gamepad` directly so this assembly
+/// step can be exercised without a live backend.
+fn connection_info(
+    name: &str,
+    uuid: [u8; 16],
+    vendor_id: Option<u16>,
+    product_id: Option<u16>,
+) -> ConnectionInfo {
+    ConnectionInfo {
+        name: name.to_string(),
+        uuid,
+        vendor_id,
+        product_id,
+    }
+}
+
+fn button_for(code: Code) -> Button {
+    match code.to_axis_or_btn() {
+        Some(AxisOrBtn::Btn(button)) => button,
+        _ => Button::Unknown,
+    }
+}
+
+fn axis_for(code: Code) -> Axis {
+    match code.to_axis_or_btn() {
+        Some(AxisOrBtn::Axis(axis)) => axis,
+        _ => Axis::Unknown,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn connection_info_builds_snapshot_from_native_gamepad_fields() {
+        let info = connection_info("Xbox Controller", [1; 16], Some(0x045e), Some(0x028e));
+
+        assert_eq!(info.name, "Xbox Controller");
+        assert_eq!(info.uuid, [1; 16]);
+        assert_eq!(info.vendor_id, Some(0x045e));
+        assert_eq!(info.product_id, Some(0x028e));
+    }
+
+    #[test]
+    fn button_for_resolves_a_known_code_to_its_button() {
+        let code = Button::South.to_nec().unwrap();
+        assert_eq!(button_for(code), Button::South);
+    }
+
+    #[test]
+    fn button_for_falls_back_to_unknown_for_an_axis_code() {
+        let code = Axis::LeftStickX.to_nec().unwrap();
+        assert_eq!(button_for(code), Button::Unknown);
+    }
+
+    #[test]
+    fn axis_for_resolves_a_known_code_to_its_axis() {
+        let code = Axis::LeftStickX.to_nec().unwrap();
+        assert_eq!(axis_for(code), Axis::LeftStickX);
+    }
+
+    #[test]
+    fn axis_for_falls_back_to_unknown_for_a_button_code() {
+        let code = Button::South.to_nec().unwrap();
+        assert_eq!(axis_for(code), Axis::Unknown);
+    }
+
+    // `next_event`'s dispatch on `gilrs_core::EventType` (including the `Connected`
+    // arm that calls `connection_info` above) drives a real `gilrs_core::Gilrs`/
+    // `Gamepad`, which this crate only depends on through Cargo and has no
+    // in-tree way to construct for a test. The translation logic it's built from —
+    // `connection_info`, `button_for`, `axis_for` — is covered individually instead.
+}